@@ -29,11 +29,14 @@
 //! Owning an instance of an SPI bus guarantees exclusive access, this is, we have the guarantee no other
 //! piece of code will try to use the bus while we own it.
 //!
-//! There's 3 bus traits, depending on the bus capabilities.
+//! There's 3 bus traits, depending on the bus capabilities, plus [`SpiBusFlush`] which all of them require.
 //!
 //! - [`SpiBus`]: Read-write access. This is the most commonly used.
 //! - [`SpiBusRead`]: Read-only access, for example a bus with a MISO pin but no MOSI pin.
 //! - [`SpiBusWrite`]: Read-write access, for example a bus with a MOSI pin but no MISO pin.
+//! - [`SpiBusFlush`]: Blocks until all submitted words have actually been clocked out. Required by
+//!   both [`SpiBusRead`] and [`SpiBusWrite`], so it's always available regardless of which of the
+//!   above you implement.
 //!
 //! ## Device
 //!
@@ -59,7 +62,7 @@
 //! By using [`SpiDevice`], your driver will cooperate nicely with other drivers for other devices in the same shared SPI bus.
 //!
 //! ```
-//! # use embedded_hal::spi::blocking::{SpiBus, SpiBusRead, SpiBusWrite, SpiDevice};
+//! # use embedded_hal::spi::blocking::{Operation, SpiBus, SpiBusRead, SpiBusWrite, SpiDevice};
 //! pub struct MyDriver<SPI> {
 //!     spi: SPI,
 //! }
@@ -77,10 +80,8 @@
 //!         let mut buf = [0; 2];
 //!
 //!         // `transaction` asserts and deasserts CS for us. No need to do it manually!
-//!         self.spi.transaction(|bus| {
-//!             bus.write(&[0x90])?;
-//!             bus.read(&mut buf)
-//!         }).map_err(MyError::Spi)?;
+//!         self.spi.transaction(&mut [Operation::Write(&[0x90]), Operation::Read(&mut buf)])
+//!             .map_err(MyError::Spi)?;
 //!
 //!         Ok(buf)
 //!     }
@@ -131,7 +132,7 @@
 //!
 //! # For HAL authors
 //!
-//! HALs **must** implement [`SpiBus`], [`SpiBusRead`] and [`SpiBusWrite`]. Users can combine the bus together with the CS pin (which should
+//! HALs **must** implement [`SpiBus`], [`SpiBusRead`], [`SpiBusWrite`] and [`SpiBusFlush`]. Users can combine the bus together with the CS pin (which should
 //! implement [`OutputPin`]) using HAL-independent [`SpiDevice`] implementations such as [`ExclusiveDevice`].
 //!
 //! HALs may additionally implement [`SpiDevice`] to **take advantage of hardware CS management**, which may provide some performance
@@ -141,19 +142,46 @@
 //! HALs **must not** add infrastructure for sharing at the [`SpiBus`] level. User code owning a [`SpiBus`] must have the guarantee
 //! of exclusive access.
 
+use core::cell::RefCell;
 use core::fmt::Debug;
 
-use crate::{digital::blocking::OutputPin, spi::ErrorType};
+use crate::{delay::blocking::DelayUs, digital::blocking::OutputPin, spi::ErrorType};
 
 use super::{Error, ErrorKind};
 
+/// A single operation within an SPI [transaction](SpiDevice::transaction).
+///
+/// This allows composing a transaction out of many read, write or transfer operations without
+/// needing a closure, so it can be built up at runtime (e.g. from a driver's command table) and
+/// forwarded across layers that aren't generic over a closure type.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation<'a, Word: 'static = u8> {
+    /// Read data into the provided buffer.
+    ///
+    /// Equivalent to [`SpiBusRead::read`].
+    Read(&'a mut [Word]),
+    /// Write data from the provided buffer, discarding read data.
+    ///
+    /// Equivalent to [`SpiBusWrite::write`].
+    Write(&'a [Word]),
+    /// Write data out while reading data into the provided buffers, with `write` sent on MOSI
+    /// and the simultaneously received words stored into `read`.
+    ///
+    /// Equivalent to [`SpiBus::transfer`].
+    Transfer(&'a mut [Word], &'a [Word]),
+    /// Write data out while reading data in, using a single buffer for both.
+    ///
+    /// Equivalent to [`SpiBus::transfer_in_place`].
+    TransferInPlace(&'a mut [Word]),
+}
+
 /// SPI device trait
 ///
 /// SpiDevice represents ownership over a single SPI device on a (possibly shared) bus, selected
 /// with a CS pin.
 ///
 /// See the [module-level documentation](self) for important usage information.
-pub trait SpiDevice: ErrorType {
+pub trait SpiDevice<Word: Copy = u8>: ErrorType {
     /// SPI Bus type for this device.
     type Bus: ErrorType;
 
@@ -161,79 +189,94 @@ pub trait SpiDevice: ErrorType {
     ///
     /// - Locks the bus
     /// - Asserts the CS (Chip Select) pin.
-    /// - Calls `f` with an exclusive reference to the bus, which can then be used to do transfers against the device.
+    /// - Runs the `operations` in order, dispatching each one to the appropriate method on the bus.
     /// - Deasserts the CS pin.
     /// - Unlocks the bus.
     ///
     /// The lock mechanism is implementation-defined. The only requirement is it must prevent two
     /// transactions from executing concurrently against the same bus. Examples of implementations are:
     /// critical sections, blocking mutexes, or returning an error or panicking if the bus is already busy.
-    fn transaction<R>(
-        &mut self,
-        f: impl FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as ErrorType>::Error>,
-    ) -> Result<R, Self::Error>;
+    ///
+    /// CS is deasserted even if an operation returns an error.
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error>;
 
     /// Do a write within a transaction.
     ///
-    /// This is a convenience method equivalent to `device.transaction(|bus| bus.write(buf))`.
+    /// This is a convenience method equivalent to `device.transaction(&mut [Operation::Write(buf)])`.
     ///
     /// See also: [`SpiDevice::transaction`], [`SpiBusWrite::write`]
-    fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error>
+    fn write(&mut self, buf: &[Word]) -> Result<(), Self::Error>
     where
-        Self::Bus: SpiBusWrite,
+        Self::Bus: SpiBusWrite<Word>,
     {
-        self.transaction(|bus| bus.write(buf))
+        self.transaction(&mut [Operation::Write(buf)])
     }
 
     /// Do a read within a transaction.
     ///
-    /// This is a convenience method equivalent to `device.transaction(|bus| bus.read(buf))`.
+    /// This is a convenience method equivalent to `device.transaction(&mut [Operation::Read(buf)])`.
     ///
     /// See also: [`SpiDevice::transaction`], [`SpiBusRead::read`]
-    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>
+    fn read(&mut self, buf: &mut [Word]) -> Result<(), Self::Error>
     where
-        Self::Bus: SpiBusRead,
+        Self::Bus: SpiBusRead<Word>,
     {
-        self.transaction(|bus| bus.read(buf))
+        self.transaction(&mut [Operation::Read(buf)])
     }
 
     /// Do a transfer within a transaction.
     ///
-    /// This is a convenience method equivalent to `device.transaction(|bus| bus.transfer(read, write))`.
+    /// This is a convenience method equivalent to `device.transaction(&mut [Operation::Transfer(read, write)])`.
     ///
     /// See also: [`SpiDevice::transaction`], [`SpiBus::transfer`]
-    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error>
+    fn transfer(&mut self, read: &mut [Word], write: &[Word]) -> Result<(), Self::Error>
     where
-        Self::Bus: SpiBus,
+        Self::Bus: SpiBus<Word>,
     {
-        self.transaction(|bus| bus.transfer(read, write))
+        self.transaction(&mut [Operation::Transfer(read, write)])
     }
 
     /// Do an in-place transfer within a transaction.
     ///
-    /// This is a convenience method equivalent to `device.transaction(|bus| bus.transfer_in_place(buf))`.
+    /// This is a convenience method equivalent to `device.transaction(&mut [Operation::TransferInPlace(buf)])`.
     ///
     /// See also: [`SpiDevice::transaction`], [`SpiBus::transfer_in_place`]
-    fn transfer_in_place(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>
+    fn transfer_in_place(&mut self, buf: &mut [Word]) -> Result<(), Self::Error>
     where
-        Self::Bus: SpiBus,
+        Self::Bus: SpiBus<Word>,
     {
-        self.transaction(|bus| bus.transfer_in_place(buf))
+        self.transaction(&mut [Operation::TransferInPlace(buf)])
     }
 }
 
-impl<T: SpiDevice> SpiDevice for &mut T {
+impl<T: SpiDevice<Word>, Word: Copy> SpiDevice<Word> for &mut T {
     type Bus = T::Bus;
-    fn transaction<R>(
-        &mut self,
-        f: impl FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as ErrorType>::Error>,
-    ) -> Result<R, Self::Error> {
-        T::transaction(self, f)
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        T::transaction(self, operations)
+    }
+}
+
+/// Flushing for SPI buses
+///
+/// Some bus implementations (DMA-backed or kernel-backed drivers) buffer or pipeline transfers,
+/// so words handed to `write`/`transfer` may not have been clocked out onto the wire yet by the
+/// time those calls return. [`SpiBusFlush::flush`] blocks until all previously submitted words
+/// have actually been sent, which a [`SpiDevice`] implementation must do before deasserting CS.
+pub trait SpiBusFlush: ErrorType {
+    /// Block until all operations have completed and the bus is idle.
+    ///
+    /// See the [trait-level documentation](SpiBusFlush) for details.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<T: SpiBusFlush> SpiBusFlush for &mut T {
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        T::flush(self)
     }
 }
 
 /// Read-only SPI bus
-pub trait SpiBusRead<Word: Copy = u8>: ErrorType {
+pub trait SpiBusRead<Word: Copy = u8>: SpiBusFlush {
     /// Reads `words` from the slave.
     ///
     /// The word value sent on MOSI during reading is implementation-defined,
@@ -248,7 +291,7 @@ impl<T: SpiBusRead<Word>, Word: Copy> SpiBusRead<Word> for &mut T {
 }
 
 /// Write-only SPI bus
-pub trait SpiBusWrite<Word: Copy = u8>: ErrorType {
+pub trait SpiBusWrite<Word: Copy = u8>: SpiBusFlush {
     /// Writes `words` to the slave, ignoring all the incoming words
     fn write(&mut self, words: &[Word]) -> Result<(), Self::Error>;
 }
@@ -291,6 +334,25 @@ impl<T: SpiBus<Word>, Word: Copy> SpiBus<Word> for &mut T {
     }
 }
 
+/// Run a batch of [`Operation`]s against a locked, CS-asserted bus.
+///
+/// Shared by the `SpiDevice` implementations below so each one only has to take care of locking
+/// the bus and driving CS.
+fn exec_operations<Word: Copy, Bus: SpiBus<Word> + ?Sized>(
+    bus: &mut Bus,
+    operations: &mut [Operation<'_, Word>],
+) -> Result<(), Bus::Error> {
+    for op in operations {
+        match op {
+            Operation::Read(buf) => bus.read(buf)?,
+            Operation::Write(buf) => bus.write(buf)?,
+            Operation::Transfer(read, write) => bus.transfer(read, write)?,
+            Operation::TransferInPlace(buf) => bus.transfer_in_place(buf)?,
+        }
+    }
+    Ok(())
+}
+
 /// Error type for [`ExclusiveDevice`] operations.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum ExclusiveDeviceError<BUS, CS> {
@@ -337,27 +399,496 @@ where
     type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
 }
 
-impl<BUS, CS> SpiDevice for ExclusiveDevice<BUS, CS>
+impl<BUS, CS, Word: Copy> SpiDevice<Word> for ExclusiveDevice<BUS, CS>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
+
+        let op_res = exec_operations(&mut self.bus, operations);
+
+        // On failure, it's important to still flush and deassert CS.
+        let flush_res = self.bus.flush();
+        let cs_res = self.cs.set_high();
+
+        op_res.map_err(ExclusiveDeviceError::Spi)?;
+        flush_res.map_err(ExclusiveDeviceError::Spi)?;
+        cs_res.map_err(ExclusiveDeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+/// [`SpiDevice`] implementation with exclusive access to the bus (not shared) and configurable
+/// CS assert/deassert timing.
+///
+/// This is like [`ExclusiveDevice`], but additionally waits for a configurable setup delay after
+/// asserting CS and before starting the transfer, and a configurable hold delay after the transfer
+/// and before deasserting CS. This is needed by SPI peripherals that require a minimum CS-to-clock
+/// setup/hold time, which would otherwise be violated on fast MCUs.
+pub struct ExclusiveDeviceWithDelay<BUS, CS, D> {
+    bus: BUS,
+    cs: CS,
+    delay: D,
+    cs_to_clock: u32,
+    clock_to_cs: u32,
+}
+
+impl<BUS, CS, D> ExclusiveDeviceWithDelay<BUS, CS, D> {
+    /// Create a new `ExclusiveDeviceWithDelay`.
+    ///
+    /// `cs_to_clock` is the delay in microseconds between asserting CS and the first clock edge.
+    /// `clock_to_cs` is the delay in microseconds between the last clock edge and deasserting CS.
+    pub fn new(bus: BUS, cs: CS, delay: D, cs_to_clock: u32, clock_to_cs: u32) -> Self {
+        Self {
+            bus,
+            cs,
+            delay,
+            cs_to_clock,
+            clock_to_cs,
+        }
+    }
+}
+
+impl<BUS, CS, D> ErrorType for ExclusiveDeviceWithDelay<BUS, CS, D>
 where
     BUS: ErrorType,
     CS: OutputPin,
+{
+    type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
+}
+
+impl<BUS, CS, D, Word: Copy> SpiDevice<Word> for ExclusiveDeviceWithDelay<BUS, CS, D>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+    D: DelayUs,
 {
     type Bus = BUS;
 
-    fn transaction<R>(
-        &mut self,
-        f: impl FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as ErrorType>::Error>,
-    ) -> Result<R, Self::Error> {
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
         self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
 
-        let f_res = f(&mut self.bus);
+        if self.cs_to_clock > 0 {
+            self.delay.delay_us(self.cs_to_clock);
+        }
+
+        let op_res = exec_operations(&mut self.bus, operations);
+
+        // On failure, it's important to still flush and deassert CS.
+        let flush_res = self.bus.flush();
+
+        if self.clock_to_cs > 0 {
+            self.delay.delay_us(self.clock_to_cs);
+        }
 
-        // If the closure fails, it's important to still deassert CS.
         let cs_res = self.cs.set_high();
 
-        let f_res = f_res.map_err(ExclusiveDeviceError::Spi)?;
+        op_res.map_err(ExclusiveDeviceError::Spi)?;
+        flush_res.map_err(ExclusiveDeviceError::Spi)?;
         cs_res.map_err(ExclusiveDeviceError::Cs)?;
 
-        Ok(f_res)
+        Ok(())
+    }
+}
+
+/// [`SpiDevice`] implementation with [`RefCell`]-based shared bus access.
+///
+/// This allows for sharing an [`SpiBus`], obtaining multiple [`SpiDevice`] implementations from
+/// it, each with its own CS pin.
+///
+/// Sharing is implemented with a `RefCell`. This means it has low overhead, but is not thread-safe:
+/// all the [`SpiDevice`] instances must be used in the same thread / interrupt priority level. If you
+/// need to share a bus across contexts, use [`CriticalSectionDevice`] or [`MutexDevice`] instead.
+pub struct RefCellDevice<'a, BUS, CS> {
+    bus: &'a RefCell<BUS>,
+    cs: CS,
+}
+
+impl<'a, BUS, CS> RefCellDevice<'a, BUS, CS> {
+    /// Create a new `RefCellDevice`.
+    pub fn new(bus: &'a RefCell<BUS>, cs: CS) -> Self {
+        Self { bus, cs }
+    }
+}
+
+impl<'a, BUS, CS> ErrorType for RefCellDevice<'a, BUS, CS>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
+}
+
+impl<'a, BUS, CS, Word: Copy> SpiDevice<Word> for RefCellDevice<'a, BUS, CS>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        let mut bus = self.bus.borrow_mut();
+
+        self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
+
+        let op_res = exec_operations(&mut *bus, operations);
+
+        // On failure, it's important to still flush and deassert CS.
+        let flush_res = bus.flush();
+        let cs_res = self.cs.set_high();
+
+        op_res.map_err(ExclusiveDeviceError::Spi)?;
+        flush_res.map_err(ExclusiveDeviceError::Spi)?;
+        cs_res.map_err(ExclusiveDeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+/// [`SpiDevice`] implementation with [`critical-section`](::critical_section)-based shared bus access.
+///
+/// This allows for sharing an [`SpiBus`], obtaining multiple [`SpiDevice`] implementations from
+/// it, each with its own CS pin.
+///
+/// Sharing is implemented with a `critical-section` [`Mutex`](critical_section::Mutex). A critical section is
+/// taken for the entire duration of a transaction, which allows the [`SpiDevice`] implementations to be
+/// used from any context: interrupt handlers, threads, and so on. The downside is transactions from
+/// different contexts cannot run concurrently, even if they're against different devices.
+///
+/// Requires the `critical-section` Cargo feature to be enabled, since it pulls in the optional
+/// `critical-section` dependency.
+#[cfg(feature = "critical-section")]
+pub struct CriticalSectionDevice<'a, BUS, CS> {
+    bus: &'a critical_section::Mutex<RefCell<BUS>>,
+    cs: CS,
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, BUS, CS> CriticalSectionDevice<'a, BUS, CS> {
+    /// Create a new `CriticalSectionDevice`.
+    pub fn new(bus: &'a critical_section::Mutex<RefCell<BUS>>, cs: CS) -> Self {
+        Self { bus, cs }
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, BUS, CS> ErrorType for CriticalSectionDevice<'a, BUS, CS>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, BUS, CS, Word: Copy> SpiDevice<Word> for CriticalSectionDevice<'a, BUS, CS>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        critical_section::with(|cs| {
+            let mut bus = self.bus.borrow_ref_mut(cs);
+
+            self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
+
+            let op_res = exec_operations(&mut *bus, operations);
+
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = bus.flush();
+            let cs_res = self.cs.set_high();
+
+            op_res.map_err(ExclusiveDeviceError::Spi)?;
+            flush_res.map_err(ExclusiveDeviceError::Spi)?;
+            cs_res.map_err(ExclusiveDeviceError::Cs)?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Trait for HAL [`SpiBus`] implementations that allow reconfiguring the bus.
+///
+/// A shared bus may have several devices wired to it, each requiring a different configuration
+/// (SPI mode, bit order, clock frequency, ...). Implementing this trait on a HAL's bus type lets
+/// [`SpiDeviceWithConfig`] apply each device's configuration right before it starts a transaction.
+pub trait SetConfig: ErrorType {
+    /// Configuration type used by this bus.
+    type Config;
+
+    /// Set the bus configuration.
+    fn set_config(&mut self, config: &Self::Config) -> Result<(), Self::Error>;
+}
+
+/// [`SpiDevice`] implementation with [`RefCell`]-based shared bus access and per-device
+/// configuration.
+///
+/// This is like [`RefCellDevice`], but also applies a device-specific [`SetConfig::Config`] to
+/// the bus at the start of every transaction, before asserting CS. This allows different devices
+/// on the same shared bus to each use their own SPI mode, bit order or clock frequency.
+pub struct SpiDeviceWithConfig<'a, BUS, CS>
+where
+    BUS: SetConfig,
+{
+    bus: &'a RefCell<BUS>,
+    cs: CS,
+    config: BUS::Config,
+}
+
+impl<'a, BUS, CS> SpiDeviceWithConfig<'a, BUS, CS>
+where
+    BUS: SetConfig,
+{
+    /// Create a new `SpiDeviceWithConfig`.
+    pub fn new(bus: &'a RefCell<BUS>, cs: CS, config: BUS::Config) -> Self {
+        Self { bus, cs, config }
+    }
+}
+
+impl<'a, BUS, CS> ErrorType for SpiDeviceWithConfig<'a, BUS, CS>
+where
+    BUS: SetConfig,
+    CS: OutputPin,
+{
+    type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
+}
+
+impl<'a, BUS, CS, Word: Copy> SpiDevice<Word> for SpiDeviceWithConfig<'a, BUS, CS>
+where
+    BUS: SetConfig + SpiBus<Word>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        let mut bus = self.bus.borrow_mut();
+
+        bus.set_config(&self.config)
+            .map_err(ExclusiveDeviceError::Spi)?;
+
+        self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
+
+        let op_res = exec_operations(&mut *bus, operations);
+
+        // On failure, it's important to still flush and deassert CS.
+        let flush_res = bus.flush();
+        let cs_res = self.cs.set_high();
+
+        op_res.map_err(ExclusiveDeviceError::Spi)?;
+        flush_res.map_err(ExclusiveDeviceError::Spi)?;
+        cs_res.map_err(ExclusiveDeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+/// [`SpiDevice`] implementation with `std::sync::Mutex`-based shared bus access.
+///
+/// This allows for sharing an [`SpiBus`], obtaining multiple [`SpiDevice`] implementations from
+/// it, each with its own CS pin.
+///
+/// Sharing is implemented with a `std::sync::Mutex`. It allows using the [`SpiDevice`] instances
+/// from separate threads, at the cost of requiring the `std` Cargo feature. [`CriticalSectionDevice`]
+/// already covers cross-context sharing on `no_std` targets; `MutexDevice` exists alongside it as the
+/// natural `std`-native choice, letting multiple real OS threads block on the bus instead of taking
+/// a global critical section. If you're on `no_std`, use [`CriticalSectionDevice`] instead.
+#[cfg(feature = "std")]
+pub struct MutexDevice<'a, BUS, CS> {
+    bus: &'a std::sync::Mutex<BUS>,
+    cs: CS,
+}
+
+#[cfg(feature = "std")]
+impl<'a, BUS, CS> MutexDevice<'a, BUS, CS> {
+    /// Create a new `MutexDevice`.
+    pub fn new(bus: &'a std::sync::Mutex<BUS>, cs: CS) -> Self {
+        Self { bus, cs }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, BUS, CS> ErrorType for MutexDevice<'a, BUS, CS>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = ExclusiveDeviceError<BUS::Error, CS::Error>;
+}
+
+#[cfg(feature = "std")]
+impl<'a, BUS, CS, Word: Copy> SpiDevice<Word> for MutexDevice<'a, BUS, CS>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        // If a panic happened while the mutex was locked, there's no way the bus is left in a
+        // consistent state, so it doesn't make sense to keep using it. Propagate the poisoning.
+        let mut bus = self.bus.lock().unwrap();
+
+        self.cs.set_low().map_err(ExclusiveDeviceError::Cs)?;
+
+        let op_res = exec_operations(&mut *bus, operations);
+
+        // On failure, it's important to still flush and deassert CS.
+        let flush_res = bus.flush();
+        let cs_res = self.cs.set_high();
+
+        op_res.map_err(ExclusiveDeviceError::Spi)?;
+        flush_res.map_err(ExclusiveDeviceError::Spi)?;
+        cs_res.map_err(ExclusiveDeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockError;
+
+    impl Error for MockError {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingBus {
+        log: Vec<&'static str>,
+        fail_on: Option<&'static str>,
+    }
+
+    impl RecordingBus {
+        fn record(&mut self, op: &'static str) -> Result<(), MockError> {
+            self.log.push(op);
+            if self.fail_on == Some(op) {
+                Err(MockError)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl ErrorType for RecordingBus {
+        type Error = MockError;
+    }
+
+    impl SpiBusFlush for RecordingBus {
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            self.record("flush")
+        }
+    }
+
+    impl SpiBusRead<u8> for RecordingBus {
+        fn read(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+            self.record("read")
+        }
+    }
+
+    impl SpiBusWrite<u8> for RecordingBus {
+        fn write(&mut self, _words: &[u8]) -> Result<(), Self::Error> {
+            self.record("write")
+        }
+    }
+
+    impl SpiBus<u8> for RecordingBus {
+        fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Self::Error> {
+            self.record("transfer")
+        }
+
+        fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+            self.record("transfer_in_place")
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingCs {
+        log: Vec<&'static str>,
+    }
+
+    impl ErrorType for RecordingCs {
+        type Error = MockError;
+    }
+
+    impl OutputPin for RecordingCs {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.log.push("cs_low");
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.log.push("cs_high");
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingDelay {
+        log: Vec<u32>,
+    }
+
+    impl DelayUs for RecordingDelay {
+        fn delay_us(&mut self, us: u32) {
+            self.log.push(us);
+        }
+    }
+
+    #[test]
+    fn transaction_asserts_cs_once_and_flushes_before_deassert() {
+        let mut dev = ExclusiveDevice::new(RecordingBus::default(), RecordingCs::default());
+
+        let mut buf = [0u8; 1];
+        dev.transaction(&mut [Operation::Write(&[1]), Operation::Read(&mut buf)])
+            .unwrap();
+
+        assert_eq!(dev.cs.log, ["cs_low", "cs_high"]);
+        assert_eq!(dev.bus.log, ["write", "read", "flush"]);
+    }
+
+    #[test]
+    fn transaction_still_flushes_and_deasserts_cs_on_error() {
+        let mut bus = RecordingBus::default();
+        bus.fail_on = Some("write");
+        let mut dev = ExclusiveDevice::new(bus, RecordingCs::default());
+
+        let mut buf = [0u8; 1];
+        let err = dev
+            .transaction(&mut [Operation::Write(&[1]), Operation::Read(&mut buf)])
+            .unwrap_err();
+
+        assert!(matches!(err, ExclusiveDeviceError::Spi(_)));
+        // The failing write short-circuits the remaining operations, but CS must still be
+        // deasserted and the bus still flushed.
+        assert_eq!(dev.cs.log, ["cs_low", "cs_high"]);
+        assert_eq!(dev.bus.log, ["write", "flush"]);
+    }
+
+    #[test]
+    fn setup_and_hold_delays_are_applied() {
+        let mut dev = ExclusiveDeviceWithDelay::new(
+            RecordingBus::default(),
+            RecordingCs::default(),
+            RecordingDelay::default(),
+            10,
+            20,
+        );
+
+        dev.transaction(&mut [Operation::Write(&[1])]).unwrap();
+
+        assert_eq!(dev.cs.log, ["cs_low", "cs_high"]);
+        assert_eq!(dev.bus.log, ["write", "flush"]);
+        assert_eq!(dev.delay.log, [10, 20]);
     }
 }